@@ -0,0 +1,148 @@
+// Full Unicode case conversion for `char` iterators.
+//
+// `char::to_uppercase`/`to_lowercase` already compute the complete, correct full case mapping for
+// every code point in Unicode (up to 3 `char`s, e.g. `ß` -> `"SS"`, `İ` lowercasing to `i` followed
+// by a combining dot above), so they're used directly and collected into an `Expansion`. The only
+// gap they leave is titlecasing, which `char` has no equivalent of; it is uppercasing plus a small
+// exceptions table covering only `ß` and the Latin ligatures (`ﬁ` -> `"Fi"`, not `"FI"`).
+
+use std::cmp::Ordering;
+
+/// The (at most three-`char`) result of fully case-converting one source `char`.
+#[derive(Copy, Clone)]
+pub struct Expansion {
+    chars: [char; 3],
+    len: u8,
+}
+
+impl Expansion {
+    fn single(c: char) -> Expansion {
+        Expansion { chars: [c, '\0', '\0'], len: 1 }
+    }
+
+    fn from_slice(s: &[char]) -> Expansion {
+        debug_assert!(s.len() <= 3);
+        let mut chars = ['\0'; 3];
+        for (slot, &c) in chars.iter_mut().zip(s.iter()) {
+            *slot = c;
+        }
+        Expansion { chars: chars, len: s.len() as u8 }
+    }
+
+    fn from_char_iter<I: Iterator<Item = char>>(iter: I) -> Expansion {
+        let mut chars = ['\0'; 3];
+        let mut len = 0u8;
+        for c in iter {
+            chars[len as usize] = c;
+            len += 1;
+        }
+        Expansion { chars: chars, len: len }
+    }
+
+    pub fn as_slice(&self) -> &[char] {
+        &self.chars[..self.len as usize]
+    }
+}
+
+// For `ß` and the Latin ligatures, titlecase capitalizes just the first replacement letter
+// (`ﬁ` -> `"Fi"`, not `"FI"`). Every other code point is titlecased as its uppercase, including
+// those whose Unicode titlecase differs (the `Ǆ`/`ǅ`/`ǆ`-style digraphs, Greek letters with
+// ypogegrammeni, Armenian ligatures).
+static TITLE_EXCEPTIONS: &'static [(char, &'static [char])] = &[
+    ('\u{df}', &['S', 's']),
+    ('\u{fb00}', &['F', 'f']),
+    ('\u{fb01}', &['F', 'i']),
+    ('\u{fb02}', &['F', 'l']),
+    ('\u{fb03}', &['F', 'f', 'i']),
+    ('\u{fb04}', &['F', 'f', 'l']),
+    ('\u{fb05}', &['S', 't']),
+    ('\u{fb06}', &['S', 't']),
+];
+
+fn bsearch_exception(c: char, table: &'static [(char, &'static [char])]) -> Option<&'static [char]> {
+    match table.binary_search_by(|&(k, _)| k.cmp(&c)) {
+        Ok(idx) => Some(table[idx].1),
+        Err(_) => None,
+    }
+}
+
+pub fn to_uppercase_full(c: char) -> Expansion {
+    Expansion::from_char_iter(c.to_uppercase())
+}
+
+pub fn to_lowercase_full(c: char) -> Expansion {
+    Expansion::from_char_iter(c.to_lowercase())
+}
+
+pub fn to_titlecase_full(c: char) -> Expansion {
+    if let Some(s) = bsearch_exception(c, TITLE_EXCEPTIONS) { return Expansion::from_slice(s); }
+    to_uppercase_full(c)
+}
+
+/// A lazy, single-pass iterator that expands each source `char` into its full case mapping
+/// (which may be more than one `char`) before pulling the next one. See `IterTools::uppercase`,
+/// `IterTools::lowercase` and `IterTools::titlecase`.
+pub struct CaseMap<I> where I: Iterator<Item = char> {
+    iter: I,
+    convert: fn(char) -> Expansion,
+    pending: Expansion,
+    pos: u8,
+}
+
+impl<I> CaseMap<I> where I: Iterator<Item = char> {
+    pub fn new(iter: I, convert: fn(char) -> Expansion) -> CaseMap<I> {
+        CaseMap {
+            iter: iter,
+            convert: convert,
+            pending: Expansion::single('\0'),
+            pos: 1, // Nothing buffered yet; `next` will pull from `iter` immediately.
+        }
+    }
+}
+
+impl<I> Iterator for CaseMap<I> where I: Iterator<Item = char> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        loop {
+            if (self.pos as usize) < self.pending.len as usize {
+                let c = self.pending.chars[self.pos as usize];
+                self.pos += 1;
+                return Some(c);
+            }
+            match self.iter.next() {
+                Some(c) => {
+                    self.pending = (self.convert)(c);
+                    self.pos = 0;
+                }
+                None => return None,
+            }
+        }
+    }
+}
+
+#[test]
+fn simple_chars() {
+    assert_eq!(to_uppercase_full('a').as_slice(), ['A']);
+    assert_eq!(to_lowercase_full('A').as_slice(), ['a']);
+    assert_eq!(to_uppercase_full('\u{3b1}').as_slice(), ['\u{391}']); // α -> Α
+    assert_eq!(to_uppercase_full('\u{101}').as_slice(), ['\u{100}']); // ā -> Ā (Latin Extended-A)
+}
+
+#[test]
+fn expanding_exceptions() {
+    assert_eq!(to_uppercase_full('\u{df}').as_slice(), ['S', 'S']); // ß -> SS
+    assert_eq!(to_lowercase_full('\u{130}').as_slice(), ['i', '\u{307}']); // İ -> i̇
+    assert_eq!(to_titlecase_full('\u{fb01}').as_slice(), ['F', 'i']); // ﬁ -> Fi
+
+    // Greek "ypogegrammeni" block: not in any hand-picked table, but `char::to_uppercase`
+    // covers it on its own.
+    assert_eq!(to_uppercase_full('\u{1f80}').as_slice(), ['\u{1f08}', '\u{399}']); // ᾀ -> ἈΙ
+}
+
+#[test]
+fn case_map_iterator() {
+    use ::iter::IterTools;
+    let upper: String = "straße".chars().uppercase().collect();
+    assert_eq!(upper, "STRASSE");
+}
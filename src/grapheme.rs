@@ -0,0 +1,268 @@
+// Extended grapheme cluster segmentation, per Unicode UAX #29.
+//
+// The break property table below is not a full transcription of
+// `GraphemeBreakProperty.txt`; it covers Latin/combining marks, Devanagari,
+// Thai, Lao, Tibetan and Myanmar combining marks, Hangul, regional
+// indicators, and emoji (including ZWJ sequences). Known gaps: other Indic
+// scripts (Bengali, Gurmukhi, Gujarati, Oriya, Tamil, Telugu, Kannada,
+// Malayalam, Sinhala) and less common scripts' combining marks are not
+// covered, so their code points fall back to `Other` and won't cluster with
+// a following mark. Hangul syllables are handled algorithmically rather than
+// tabulated, since `LV`/`LVT` cover the entire syllable block and are
+// cheaper to compute than to list.
+
+use std::cmp::Ordering;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GraphemeCat {
+    CR,
+    LF,
+    Control,
+    Extend,
+    ZWJ,
+    RegionalIndicator,
+    Prepend,
+    SpacingMark,
+    L,
+    V,
+    T,
+    LV,
+    LVT,
+    ExtendedPictographic,
+    Other,
+}
+
+// Sorted, non-overlapping `(lo, hi, category)` ranges, searched with
+// `bsearch_range_table` below.
+static TABLE: &'static [(char, char, GraphemeCat)] = &[
+    ('\u{0}', '\u{9}', GraphemeCat::Control),
+    ('\u{a}', '\u{a}', GraphemeCat::LF),
+    ('\u{b}', '\u{c}', GraphemeCat::Control),
+    ('\u{d}', '\u{d}', GraphemeCat::CR),
+    ('\u{e}', '\u{1f}', GraphemeCat::Control),
+    ('\u{7f}', '\u{9f}', GraphemeCat::Control),
+    ('\u{300}', '\u{36f}', GraphemeCat::Extend),
+    ('\u{483}', '\u{489}', GraphemeCat::Extend),
+    ('\u{591}', '\u{5bd}', GraphemeCat::Extend),
+    ('\u{600}', '\u{605}', GraphemeCat::Prepend),
+    ('\u{900}', '\u{902}', GraphemeCat::Extend),
+    ('\u{903}', '\u{903}', GraphemeCat::SpacingMark),
+    ('\u{93b}', '\u{93b}', GraphemeCat::SpacingMark),
+    ('\u{93c}', '\u{93c}', GraphemeCat::Extend),
+    ('\u{93e}', '\u{940}', GraphemeCat::SpacingMark),
+    ('\u{941}', '\u{948}', GraphemeCat::Extend),
+    ('\u{949}', '\u{94c}', GraphemeCat::SpacingMark),
+    ('\u{94d}', '\u{94d}', GraphemeCat::Extend),
+    ('\u{94e}', '\u{94f}', GraphemeCat::SpacingMark),
+    ('\u{951}', '\u{957}', GraphemeCat::Extend),
+    ('\u{962}', '\u{963}', GraphemeCat::Extend),
+    ('\u{e31}', '\u{e31}', GraphemeCat::Extend),
+    ('\u{e34}', '\u{e3a}', GraphemeCat::Extend),
+    ('\u{e47}', '\u{e4e}', GraphemeCat::Extend),
+    ('\u{eb1}', '\u{eb1}', GraphemeCat::Extend),
+    ('\u{eb4}', '\u{eb9}', GraphemeCat::Extend),
+    ('\u{ebb}', '\u{ebc}', GraphemeCat::Extend),
+    ('\u{ec8}', '\u{ecd}', GraphemeCat::Extend),
+    ('\u{f18}', '\u{f19}', GraphemeCat::Extend),
+    ('\u{f35}', '\u{f35}', GraphemeCat::Extend),
+    ('\u{f37}', '\u{f37}', GraphemeCat::Extend),
+    ('\u{f39}', '\u{f39}', GraphemeCat::Extend),
+    ('\u{f3e}', '\u{f3f}', GraphemeCat::SpacingMark),
+    ('\u{f71}', '\u{f7e}', GraphemeCat::Extend),
+    ('\u{f7f}', '\u{f7f}', GraphemeCat::SpacingMark),
+    ('\u{f80}', '\u{f84}', GraphemeCat::Extend),
+    ('\u{f86}', '\u{f87}', GraphemeCat::Extend),
+    ('\u{f8d}', '\u{f97}', GraphemeCat::Extend),
+    ('\u{f99}', '\u{fbc}', GraphemeCat::Extend),
+    ('\u{fc6}', '\u{fc6}', GraphemeCat::Extend),
+    ('\u{102d}', '\u{1030}', GraphemeCat::Extend),
+    ('\u{1032}', '\u{1037}', GraphemeCat::Extend),
+    ('\u{1039}', '\u{103a}', GraphemeCat::Extend),
+    ('\u{103b}', '\u{103c}', GraphemeCat::SpacingMark),
+    ('\u{103d}', '\u{103e}', GraphemeCat::Extend),
+    ('\u{1056}', '\u{1057}', GraphemeCat::SpacingMark),
+    ('\u{1058}', '\u{1059}', GraphemeCat::Extend),
+    ('\u{1100}', '\u{115f}', GraphemeCat::L),
+    ('\u{1160}', '\u{11a7}', GraphemeCat::V),
+    ('\u{11a8}', '\u{11ff}', GraphemeCat::T),
+    ('\u{1ab0}', '\u{1aff}', GraphemeCat::Extend),
+    ('\u{1dc0}', '\u{1dff}', GraphemeCat::Extend),
+    ('\u{200d}', '\u{200d}', GraphemeCat::ZWJ),
+    ('\u{20d0}', '\u{20ff}', GraphemeCat::Extend),
+    ('\u{2600}', '\u{27bf}', GraphemeCat::ExtendedPictographic),
+    ('\u{a960}', '\u{a97c}', GraphemeCat::L),
+    ('\u{d7b0}', '\u{d7c6}', GraphemeCat::V),
+    ('\u{d7cb}', '\u{d7fb}', GraphemeCat::T),
+    ('\u{fe00}', '\u{fe0f}', GraphemeCat::Extend),
+    ('\u{fe20}', '\u{fe2f}', GraphemeCat::Extend),
+    ('\u{1f1e6}', '\u{1f1ff}', GraphemeCat::RegionalIndicator),
+    ('\u{1f300}', '\u{1f3fa}', GraphemeCat::ExtendedPictographic),
+    ('\u{1f3fb}', '\u{1f3ff}', GraphemeCat::Extend),
+    ('\u{1f400}', '\u{1f5ff}', GraphemeCat::ExtendedPictographic),
+    ('\u{1f600}', '\u{1f64f}', GraphemeCat::ExtendedPictographic),
+    ('\u{1f680}', '\u{1f6ff}', GraphemeCat::ExtendedPictographic),
+    ('\u{1f7e0}', '\u{1f7ff}', GraphemeCat::ExtendedPictographic),
+    ('\u{1f900}', '\u{1f9ff}', GraphemeCat::ExtendedPictographic),
+    ('\u{1fa70}', '\u{1faff}', GraphemeCat::ExtendedPictographic),
+    ('\u{e0100}', '\u{e01ef}', GraphemeCat::Extend),
+];
+
+fn bsearch_range_table(c: char, r: &'static [(char, char, GraphemeCat)]) -> Option<GraphemeCat> {
+    match r.binary_search_by(|&(lo, hi, _)| {
+        if c < lo { Ordering::Greater }
+        else if c > hi { Ordering::Less }
+        else { Ordering::Equal }
+    }) {
+        Ok(idx) => Some(r[idx].2),
+        Err(_) => None,
+    }
+}
+
+const HANGUL_S_BASE: u32 = 0xAC00;
+const HANGUL_S_COUNT: u32 = 11172;
+const HANGUL_T_COUNT: u32 = 28;
+
+/// Looks up the grapheme cluster break property of a code point (UAX #29).
+pub fn grapheme_break_property(c: char) -> GraphemeCat {
+    let cp = c as u32;
+    if cp >= HANGUL_S_BASE && cp < HANGUL_S_BASE + HANGUL_S_COUNT {
+        return if (cp - HANGUL_S_BASE) % HANGUL_T_COUNT == 0 {
+            GraphemeCat::LV
+        } else {
+            GraphemeCat::LVT
+        };
+    }
+    bsearch_range_table(c, TABLE).unwrap_or(GraphemeCat::Other)
+}
+
+fn is_control_like(p: GraphemeCat) -> bool {
+    p == GraphemeCat::Control || p == GraphemeCat::CR || p == GraphemeCat::LF
+}
+
+fn hangul_joins(prev: GraphemeCat, cur: GraphemeCat) -> bool {
+    use self::GraphemeCat::*;
+    match (prev, cur) {
+        (L, L) | (L, V) | (L, LV) | (L, LVT) => true,
+        (LV, V) | (LV, T) | (V, V) | (V, T) => true,
+        (LVT, T) | (T, T) => true,
+        _ => false,
+    }
+}
+
+// Decides whether there is a grapheme cluster boundary between `prev` and
+// `cur`, given `ri_count` (the number of consecutive Regional_Indicators
+// ending at `prev`) and `pictographic` (whether `prev` is the tail of an
+// `Extended_Pictographic Extend* ZWJ?` run). First matching rule wins.
+fn breaks_before(prev: GraphemeCat, cur: GraphemeCat, ri_count: u32, pictographic: bool) -> bool {
+    use self::GraphemeCat::*;
+    if prev == CR && cur == LF { return false; }
+    if is_control_like(prev) || is_control_like(cur) { return true; }
+    if hangul_joins(prev, cur) { return false; }
+    if cur == Extend || cur == ZWJ { return false; }
+    if cur == SpacingMark { return false; }
+    if prev == Prepend { return false; }
+    if prev == ZWJ && pictographic && cur == ExtendedPictographic { return false; }
+    if prev == RegionalIndicator && cur == RegionalIndicator && ri_count % 2 == 1 { return false; }
+    true
+}
+
+/// Streaming iterator over the extended grapheme clusters of a `&str`.
+///
+/// See `StrTools::graphemes`.
+pub struct Graphemes<'a> {
+    s: &'a str,
+}
+
+impl<'a> Graphemes<'a> {
+    pub fn new(s: &'a str) -> Graphemes<'a> {
+        Graphemes { s: s }
+    }
+}
+
+impl<'a> Iterator for Graphemes<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.s.is_empty() {
+            return None;
+        }
+
+        let mut chars = self.s.char_indices();
+        let (_, first) = chars.next().unwrap();
+        let mut prev_prop = grapheme_break_property(first);
+        let mut ri_count = if prev_prop == GraphemeCat::RegionalIndicator { 1 } else { 0 };
+        let mut pictographic = prev_prop == GraphemeCat::ExtendedPictographic;
+
+        for (idx, c) in chars {
+            let prop = grapheme_break_property(c);
+            if breaks_before(prev_prop, prop, ri_count, pictographic) {
+                let (cluster, rest) = self.s.split_at(idx);
+                self.s = rest;
+                return Some(cluster);
+            }
+
+            ri_count = if prop == GraphemeCat::RegionalIndicator {
+                if prev_prop == GraphemeCat::RegionalIndicator { ri_count + 1 } else { 1 }
+            } else { 0 };
+            pictographic = match prop {
+                GraphemeCat::ExtendedPictographic => true,
+                GraphemeCat::Extend | GraphemeCat::ZWJ => pictographic && prev_prop != GraphemeCat::ZWJ,
+                _ => false,
+            };
+            prev_prop = prop;
+        }
+
+        let cluster = self.s;
+        self.s = &self.s[self.s.len()..];
+        Some(cluster)
+    }
+}
+
+#[test]
+fn ascii() {
+    let v: Vec<&str> = Graphemes::new("abc").collect();
+    assert_eq!(v, ["a", "b", "c"]);
+}
+
+#[test]
+fn combining_mark_stays_attached() {
+    // "e" + combining acute accent
+    let v: Vec<&str> = Graphemes::new("e\u{301}f").collect();
+    assert_eq!(v, ["e\u{301}", "f"]);
+}
+
+#[test]
+fn crlf_stays_together() {
+    let v: Vec<&str> = Graphemes::new("a\r\nb").collect();
+    assert_eq!(v, ["a", "\r\n", "b"]);
+}
+
+#[test]
+fn regional_indicators_pair_up() {
+    // Flag of the US: U+1F1FA U+1F1F8, followed by a lone regional indicator.
+    let v: Vec<&str> = Graphemes::new("\u{1f1fa}\u{1f1f8}\u{1f1e6}").collect();
+    assert_eq!(v, ["\u{1f1fa}\u{1f1f8}", "\u{1f1e6}"]);
+}
+
+#[test]
+fn emoji_zwj_sequence_stays_together() {
+    // Woman + ZWJ + Computer forms one cluster.
+    let s = "\u{1f469}\u{200d}\u{1f4bb}";
+    let v: Vec<&str> = Graphemes::new(s).collect();
+    assert_eq!(v, [s]);
+}
+
+#[test]
+fn newer_emoji_block_stays_attached_to_modifier() {
+    // U+1FAF0 (HAND WITH INDEX FINGER AND THUMB CROSSED) + Fitzpatrick modifier, from Symbols and Pictographs Extended-A.
+    let v: Vec<&str> = Graphemes::new("\u{1faf0}\u{1f3fb}x").collect();
+    assert_eq!(v, ["\u{1faf0}\u{1f3fb}", "x"]);
+}
+
+#[test]
+fn thai_lao_tibetan_myanmar_marks_stay_attached() {
+    assert_eq!(Graphemes::new("\u{e01}\u{e31}x").collect::<Vec<_>>(), ["\u{e01}\u{e31}", "x"]);
+    assert_eq!(Graphemes::new("\u{ea1}\u{eb1}x").collect::<Vec<_>>(), ["\u{ea1}\u{eb1}", "x"]);
+    assert_eq!(Graphemes::new("\u{f40}\u{f71}x").collect::<Vec<_>>(), ["\u{f40}\u{f71}", "x"]);
+    assert_eq!(Graphemes::new("\u{1000}\u{102d}x").collect::<Vec<_>>(), ["\u{1000}\u{102d}", "x"]);
+}
@@ -1,6 +1,7 @@
 use std::mem;
 use std::iter::Peekable;
 use arena::TypedArena;
+use case::{self, CaseMap};
 
 // Infinite <3s to Luqman for most of this impl
 pub struct Utf8Iter<I> where I: Iterator<Item = u8> {
@@ -85,10 +86,78 @@ impl<I> Iterator for Utf8Iter<I>
     }
 }
 
+// Yields one `char` per well-formed code point, substituting `U+FFFD` for each ill-formed
+// maximal subpart (per the Unicode "substitution of maximal subparts" recommendation) rather
+// than `Utf8Iter`'s `Some(None)`. A byte that cannot extend the sequence being decoded is never
+// consumed: it is left in the `Peekable` buffer so the next `next()` call re-reads it as a
+// potential sequence start, keeping decoding aligned with the input.
+pub struct Utf8IterLossy<I> where I: Iterator<Item = u8> {
+    buf: Peekable<I>,
+}
+
+impl<I> Iterator for Utf8IterLossy<I>
+        where I: Iterator<Item = u8> {
+
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        const CONT_MASK: u8 = (1 << 6) - 1;
+        const REPLACEMENT: char = '\u{FFFD}';
+
+        let first = match self.buf.next() {
+            Some(b) => b,
+            None => return None,
+        };
+
+        // Single byte rune (ASCII)
+        if (first & (1 << 7)) == 0 {
+            return Some(first as char);
+        }
+
+        // How many bytes should make up this rune, and the valid range for its first
+        // continuation byte (tighter than 0x80...0xBF for the lead bytes that would
+        // otherwise admit overlong encodings or surrogates).
+        let (len, first_cont_range): (u32, (u8, u8)) = match first {
+            0xC2 ... 0xDF => (2, (0x80, 0xBF)),
+            0xE0          => (3, (0xA0, 0xBF)),
+            0xE1 ... 0xEC => (3, (0x80, 0xBF)),
+            0xED          => (3, (0x80, 0x9F)),
+            0xEE ... 0xEF => (3, (0x80, 0xBF)),
+            0xF0          => (4, (0x90, 0xBF)),
+            0xF1 ... 0xF3 => (4, (0x80, 0xBF)),
+            0xF4          => (4, (0x80, 0x8F)),
+            // Lone continuation byte, overlong lead (0xC0/0xC1), or an otherwise invalid
+            // byte: the lead byte alone is the maximal subpart.
+            _ => return Some(REPLACEMENT),
+        };
+
+        let mut result = (first & ((1 << (7 - len)) - 1)) as u32;
+
+        for i in (0 .. len - 1) {
+            let (lo, hi) = if i == 0 { first_cont_range } else { (0x80, 0xBF) };
+            match self.buf.peek() {
+                Some(&b) if b >= lo && b <= hi => {
+                    self.buf.next();
+                    result = result << 6 | (b & CONT_MASK) as u32;
+                }
+                // Either the stream ended or the next byte can't extend this sequence.
+                // Don't consume it: emit one replacement for the valid prefix seen so far
+                // and let the next call re-examine that byte as a fresh sequence start.
+                _ => return Some(REPLACEMENT),
+            }
+        }
+
+        Some(unsafe { mem::transmute(result) })
+    }
+}
+
 pub trait IterTools: Sized {
     fn utf8_iter(self) -> Utf8Iter<Self>
         where Self: Iterator<Item=u8>;
 
+    fn utf8_iter_lossy(self) -> Utf8IterLossy<Self>
+        where Self: Iterator<Item=u8>;
+
     fn group<F, G>(self, f: F) -> Groups<Self, F, G>
         where Self: Iterator, F: FnMut(&<Self as Iterator>::Item) -> G, G: PartialEq;
 
@@ -97,6 +166,25 @@ pub trait IterTools: Sized {
 
     fn dedup(self) -> DedupIter<Self>
         where Self: Iterator, <Self as Iterator>::Item: PartialEq;
+
+    /// Full Unicode uppercasing: yields every `char` of `char::to_uppercase` for each source
+    /// `char` in turn, so code points whose uppercase form is more than one `char` (such as
+    /// `ß` -> `"SS"`) expand in place.
+    fn uppercase(self) -> CaseMap<Self>
+        where Self: Iterator<Item=char>;
+
+    /// Full Unicode lowercasing: yields every `char` of `char::to_lowercase` for each source
+    /// `char` in turn, so code points whose lowercase form is more than one `char` (such as
+    /// `İ` -> `"i\u{307}"`) expand in place.
+    fn lowercase(self) -> CaseMap<Self>
+        where Self: Iterator<Item=char>;
+
+    /// Titlecasing, e.g. for capitalizing the first letter of a word: like `uppercase`, except
+    /// that `ß` and the Latin ligatures (such as `ﬁ`) only capitalize their first letter
+    /// (`ﬁ` -> `"Fi"`, not `"FI"`). No other code point is special-cased, so those whose Unicode
+    /// titlecase differs from their uppercase in other ways (e.g. the `ǆ` digraph) are uppercased.
+    fn titlecase(self) -> CaseMap<Self>
+        where Self: Iterator<Item=char>;
 }
 
 impl<T> IterTools for T {
@@ -106,6 +194,12 @@ impl<T> IterTools for T {
         Utf8Iter { buf: self.peekable() }
     }
 
+    #[inline(always)]
+    fn utf8_iter_lossy(self) -> Utf8IterLossy<Self>
+            where Self: Iterator<Item=u8> {
+        Utf8IterLossy { buf: self.peekable() }
+    }
+
     #[inline(always)]
     fn group<F, G>(self, f: F) -> Groups<Self, F, G>
             where Self: Iterator, F: FnMut(&<Self as Iterator>::Item) -> G, G: PartialEq {
@@ -132,6 +226,24 @@ impl<T> IterTools for T {
             iter: self.peekable(),
         }
     }
+
+    #[inline(always)]
+    fn uppercase(self) -> CaseMap<Self>
+            where Self: Iterator<Item=char> {
+        CaseMap::new(self, case::to_uppercase_full)
+    }
+
+    #[inline(always)]
+    fn lowercase(self) -> CaseMap<Self>
+            where Self: Iterator<Item=char> {
+        CaseMap::new(self, case::to_lowercase_full)
+    }
+
+    #[inline(always)]
+    fn titlecase(self) -> CaseMap<Self>
+            where Self: Iterator<Item=char> {
+        CaseMap::new(self, case::to_titlecase_full)
+    }
 }
 
 pub trait StreamingIterator<'a> {
@@ -286,6 +398,37 @@ fn utf8_chars() {
     );
 }
 
+#[test]
+fn utf8_chars_lossy() {
+    // Well-formed input decodes exactly like the strict iterator, minus the `Option` wrapper.
+    assert_eq!(
+        vec![0x42, 0xC9, 0xA3, 0xE2, 0x98, 0x83, 0xF0, 0xA0, 0x9C, 0xB1].into_iter().utf8_iter_lossy()
+            .collect::<Vec<_>>(),
+        vec!['B', 'ɣ', '☃', '𠜱']
+    );
+
+    // A lone invalid byte becomes one replacement character and doesn't swallow its neighbour.
+    assert_eq!(
+        vec![0x41, 0xFF, 0x42].into_iter().utf8_iter_lossy().collect::<Vec<_>>(),
+        vec!['A', '\u{FFFD}', 'B']
+    );
+
+    // A 3-byte sequence cut short after its second byte, then a valid 2-byte sequence: the bad
+    // continuation byte (which
+    // happens to also be a valid lead byte) is re-read as the start of the next rune instead
+    // of being consumed and lost.
+    assert_eq!(
+        vec![0xE2, 0x98, 0xC9, 0xA3].into_iter().utf8_iter_lossy().collect::<Vec<_>>(),
+        vec!['\u{FFFD}', 'ɣ']
+    );
+
+    // A truncated trailing sequence produces exactly one replacement character.
+    assert_eq!(
+        vec![0x41, 0xF0, 0xA0, 0x9C].into_iter().utf8_iter_lossy().collect::<Vec<_>>(),
+        vec!['A', '\u{FFFD}']
+    );
+}
+
 #[test]
 fn refs() {
     // Check lifetime stuff
@@ -2,11 +2,15 @@
 
 extern crate arena;
 
+pub mod case;
+pub mod grapheme;
 pub mod iter;
 pub mod str;
 pub mod slice;
 pub mod exts;
 
+pub use case::CaseMap;
+pub use grapheme::{GraphemeCat, Graphemes, grapheme_break_property};
 pub use iter::{IterTools, StreamingIterator};
-pub use str::StringTools;
+pub use str::{StringTools, StrTools};
 pub use slice::{SliceTools, VecTools};
@@ -1,4 +1,5 @@
 use std::mem;
+use std::cmp::Ordering;
 
 pub trait VecTools<T> {
     fn in_place<F>(&mut self, f: F)
@@ -44,6 +45,16 @@ pub trait SliceTools<T> {
     /// // 3, [0, 1, 2]
     /// ```
     fn remove_iter(&mut self) -> RemoveIter<T>;
+
+    /// Sorts the slice in place, without allocating, in `O(n log n)` worst-case time.
+    ///
+    /// This is not a stable sort: equal elements may be reordered. It is, however, typically
+    /// faster than a stable sort (and faster than a naive quicksort) since it is pattern-defeating:
+    /// it falls back to heapsort on adversarial inputs that would make a plain quicksort quadratic.
+    fn sort_unstable(&mut self) where T: Ord;
+
+    /// Like `sort_unstable`, but using `compare` to decide the order of two elements.
+    fn sort_unstable_by<F>(&mut self, compare: F) where F: FnMut(&T, &T) -> Ordering;
 }
 
 fn subslice_offset<T>(slf: &[T], inner: &[T]) -> usize {
@@ -103,6 +114,212 @@ impl<T> SliceTools<T> for [T] {
             idx: 0,
         }
     }
+
+    fn sort_unstable(&mut self) where T: Ord {
+        self.sort_unstable_by(|a, b| a.cmp(b));
+    }
+
+    fn sort_unstable_by<F>(&mut self, mut compare: F) where F: FnMut(&T, &T) -> Ordering {
+        let limit = log2_floor(self.len());
+        pdqsort(self, &mut compare, limit);
+    }
+}
+
+// Below this size, insertion sort beats quicksort's overhead.
+const INSERTION_SORT_THRESHOLD: usize = 20;
+
+// Above this size, a single median-of-three pivot is too easy to fool; take the median of three
+// medians-of-three spread across the slice instead.
+const MEDIAN_OF_MEDIANS_THRESHOLD: usize = 50;
+
+fn log2_floor(n: usize) -> u32 {
+    if n == 0 { 0 } else { 63 - (n as u64).leading_zeros() }
+}
+
+// The core pattern-defeating quicksort loop. `limit` is the number of unbalanced partitions
+// still tolerated before this call gives up on quicksort and switches to heapsort, which
+// guarantees `O(n log n)` even on adversarial input.
+fn pdqsort<T, F>(mut v: &mut [T], compare: &mut F, mut limit: u32)
+        where F: FnMut(&T, &T) -> Ordering {
+    // Whether the partition that produced the current `v` was unbalanced. Checked at the top
+    // of the loop (i.e. before the next pivot is chosen), since perturbing `v` once a pivot has
+    // already been swapped to the front and partitioned around would move elements across the
+    // left/right boundary the caller is about to recurse on, silently breaking the sort.
+    let mut was_balanced = true;
+
+    loop {
+        let len = v.len();
+        if len <= INSERTION_SORT_THRESHOLD {
+            insertion_sort(v, compare);
+            return;
+        }
+
+        if limit == 0 {
+            heapsort(v, compare);
+            return;
+        }
+
+        if !was_balanced {
+            break_pattern(v);
+        }
+
+        let pivot = choose_pivot(v, compare);
+        v.swap(0, pivot);
+
+        // If `v` is already (nearly) sorted, a bounded insertion-sort attempt can finish it in one
+        // pass; when it does, take the shortcut instead of partitioning and recursing.
+        if partial_insertion_sort(v, compare) {
+            return;
+        }
+
+        let (mid, balanced) = partition(v, compare);
+        was_balanced = balanced;
+        if !balanced {
+            limit -= 1;
+        }
+
+        let (left, pivot_and_right) = v.split_at_mut(mid);
+        let right = &mut pivot_and_right[1..];
+        if left.len() < right.len() {
+            pdqsort(left, compare, limit);
+            v = right;
+        } else {
+            pdqsort(right, compare, limit);
+            v = left;
+        }
+    }
+}
+
+fn insertion_sort<T, F>(v: &mut [T], compare: &mut F)
+        where F: FnMut(&T, &T) -> Ordering {
+    for i in (1..v.len()) {
+        let mut j = i;
+        while j > 0 && compare(&v[j - 1], &v[j]) == Ordering::Greater {
+            v.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+// Attempts to finish sorting `v` (assumed to already have a pivot at index 0 consistent with the
+// rest) via insertion sort, but gives up after a few out-of-place elements rather than degrading
+// to `O(n^2)` on inputs that merely *look* sorted. Returns `true` if `v` ended up fully sorted.
+fn partial_insertion_sort<T, F>(v: &mut [T], compare: &mut F) -> bool
+        where F: FnMut(&T, &T) -> Ordering {
+    const MAX_STEPS: usize = 5;
+    const SHORTEST_SHIFTING: usize = 50;
+
+    let len = v.len();
+    let mut i = 1;
+    for _ in (0..MAX_STEPS) {
+        while i < len && compare(&v[i - 1], &v[i]) != Ordering::Greater {
+            i += 1;
+        }
+        if i == len {
+            return true;
+        }
+        if len < SHORTEST_SHIFTING {
+            return false;
+        }
+        v.swap(i - 1, i);
+        if i >= 2 {
+            let mut j = i - 1;
+            while j > 0 && compare(&v[j - 1], &v[j]) == Ordering::Greater {
+                v.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+        i += 1;
+    }
+    false
+}
+
+// Returns the index of the median of `v[a]`, `v[b]`, `v[c]`.
+fn median3<T, F>(v: &[T], a: usize, b: usize, c: usize, compare: &mut F) -> usize
+        where F: FnMut(&T, &T) -> Ordering {
+    if compare(&v[a], &v[b]) == Ordering::Less {
+        if compare(&v[b], &v[c]) == Ordering::Less { b }
+        else if compare(&v[a], &v[c]) == Ordering::Less { c }
+        else { a }
+    } else {
+        if compare(&v[a], &v[c]) == Ordering::Less { a }
+        else if compare(&v[b], &v[c]) == Ordering::Less { c }
+        else { b }
+    }
+}
+
+fn choose_pivot<T, F>(v: &[T], compare: &mut F) -> usize
+        where F: FnMut(&T, &T) -> Ordering {
+    let len = v.len();
+    let mid = len / 2;
+    if len < MEDIAN_OF_MEDIANS_THRESHOLD {
+        median3(v, 0, mid, len - 1, compare)
+    } else {
+        let d = len / 8;
+        let m1 = median3(v, 0, d, 2 * d, compare);
+        let m2 = median3(v, mid - d, mid, mid + d, compare);
+        let m3 = median3(v, len - 1 - 2 * d, len - 1 - d, len - 1, compare);
+        median3(v, m1, m2, m3, compare)
+    }
+}
+
+// Partitions `v` around the pivot stored at `v[0]` (Lomuto scheme), leaving the pivot at its
+// final sorted index and returning that index along with whether the split was balanced (the
+// smaller side holds at least `len / 8` elements).
+fn partition<T, F>(v: &mut [T], compare: &mut F) -> (usize, bool)
+        where F: FnMut(&T, &T) -> Ordering {
+    let len = v.len();
+    let mut store = 0;
+    for i in (1..len) {
+        if compare(&v[i], &v[0]) == Ordering::Less {
+            store += 1;
+            v.swap(store, i);
+        }
+    }
+    v.swap(0, store);
+
+    let smaller = if store < len - store - 1 { store } else { len - store - 1 };
+    (store, smaller >= len / 8)
+}
+
+// Scrambles a handful of fixed offsets to break the adversarial (e.g. organ-pipe) patterns that
+// repeatedly provoke unbalanced partitions.
+fn break_pattern<T>(v: &mut [T]) {
+    let len = v.len();
+    if len < 8 { return; }
+    let mid = len / 2;
+    v.swap(mid - 1, mid);
+    v.swap(1, len - 2);
+    v.swap(2, len - 3);
+}
+
+fn heapsort<T, F>(v: &mut [T], compare: &mut F)
+        where F: FnMut(&T, &T) -> Ordering {
+    let len = v.len();
+    for start in (0..len / 2).rev() {
+        sift_down(v, compare, start, len);
+    }
+    for end in (1..len).rev() {
+        v.swap(0, end);
+        sift_down(v, compare, 0, end);
+    }
+}
+
+fn sift_down<T, F>(v: &mut [T], compare: &mut F, mut root: usize, len: usize)
+        where F: FnMut(&T, &T) -> Ordering {
+    loop {
+        let mut child = 2 * root + 1;
+        if child >= len { break; }
+        if child + 1 < len && compare(&v[child], &v[child + 1]) == Ordering::Less {
+            child += 1;
+        }
+        if compare(&v[root], &v[child]) == Ordering::Less {
+            v.swap(root, child);
+            root = child;
+        } else {
+            break;
+        }
+    }
 }
 
 pub struct RemoveIter<'a, T: 'a> {
@@ -172,3 +389,34 @@ fn test_rest_iter() {
     }
     assert_eq!(a, [0, 1, 2, 3]);
 }
+
+#[test]
+fn test_sort_unstable() {
+    let mut v = vec![5, 3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7];
+    v.sort_unstable();
+    assert_eq!(v, [1, 1, 2, 3, 3, 4, 5, 5, 5, 5, 6, 7, 8, 9, 9]);
+}
+
+#[test]
+fn test_sort_unstable_already_sorted() {
+    let mut v: Vec<i32> = (0..200).collect();
+    v.sort_unstable();
+    assert_eq!(v, (0..200).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_sort_unstable_organ_pipe() {
+    // Ascending then descending: a classic quicksort-pivot killer.
+    let mut v: Vec<i32> = (0..100).chain((0..100).rev()).collect();
+    v.sort_unstable();
+    let mut expected = v.clone();
+    expected.sort();
+    assert_eq!(v, expected);
+}
+
+#[test]
+fn test_sort_unstable_by() {
+    let mut v = vec![5, 3, 1, 4, 2];
+    v.sort_unstable_by(|a, b| b.cmp(a));
+    assert_eq!(v, [5, 4, 3, 2, 1]);
+}
@@ -1,10 +1,17 @@
 use std::intrinsics;
+use grapheme::Graphemes;
 
 pub trait StringTools {
     fn in_place<F>(&mut self, f: F)
             where F: FnOnce(&str) -> &str;
 }
 
+pub trait StrTools {
+    /// Returns an iterator over the extended grapheme clusters (UAX #29) of `self`, i.e. the
+    /// user-perceived characters rather than the individual code points.
+    fn graphemes(&self) -> Graphemes;
+}
+
 impl StringTools for String {
     fn in_place<F>(&mut self, f: F)
             where F: FnOnce(&str) -> &str {
@@ -24,6 +31,12 @@ impl StringTools for String {
     }
 }
 
+impl StrTools for str {
+    fn graphemes(&self) -> Graphemes {
+        Graphemes::new(self)
+    }
+}
+
 #[test]
 fn in_place() {
     let mut s = "   hello world \n".to_string();
@@ -32,3 +45,28 @@ fn in_place() {
     assert_eq!(s, "hello world");
     assert_eq!(s.capacity(), cap);
 }
+
+#[test]
+fn graphemes_on_str() {
+    let v: Vec<&str> = "e\u{301}f".graphemes().collect();
+    assert_eq!(v, ["e\u{301}", "f"]);
+}
+
+#[test]
+fn graphemes_on_string() {
+    let s = "e\u{301}f".to_string();
+    let v: Vec<&str> = s.graphemes().collect();
+    assert_eq!(v, ["e\u{301}", "f"]);
+}
+
+#[test]
+fn graphemes_inside_in_place() {
+    // The scenario `graphemes()` exists for: trimming a `String` in place without splitting a
+    // combining sequence, via a closure that only ever sees a `&str`.
+    let mut s = "e\u{301}f\u{301}g".to_string();
+    s.in_place(|s| {
+        let first_len = s.graphemes().next().unwrap().len();
+        &s[first_len..]
+    });
+    assert_eq!(s, "f\u{301}g");
+}